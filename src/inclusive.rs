@@ -0,0 +1,149 @@
+//! Inclusive-endpoint counterpart to the top-level [`crate::Ranges`].
+//!
+//! Discrete domains (integers, dates, ...) are often expressed with inclusive bounds, where
+//! `5..=10` and `11..=15` are adjacent because there is no value between `10` and `11`. The
+//! half-open [`crate::Ranges`] can't express that without forcing an off-by-one conversion, so
+//! this module provides a parallel type built directly on [`std::ops::RangeInclusive`].
+
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+
+/// A type that has a well-defined successor, used to decide when two inclusive ranges are
+/// adjacent (e.g. `10`'s successor is `11`, so `5..=10` and `11..=15` touch).
+pub trait Successor: Sized {
+    /// Returns the value immediately following `self`, or `None` if `self` is already the
+    /// largest representable value and has no successor.
+    fn checked_successor(&self) -> Option<Self>;
+}
+
+macro_rules! impl_successor_for_int {
+    ($($t:ty),*) => {
+        $(
+            impl Successor for $t {
+                fn checked_successor(&self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+            }
+        )*
+    };
+}
+
+impl_successor_for_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// Structure that manages an expanding list of inclusive ranges, coalescing both overlapping
+/// and adjacent (touching) ranges.
+///
+/// ```
+/// use ranges::inclusive::RangesInclusive;
+///
+/// let mut ranges = RangesInclusive::default();
+///
+/// ranges.add_range(5..=10);
+/// ranges.add_range(11..=15);
+///
+/// assert_eq!(ranges.to_vec(), vec![5..=15]);
+/// ```
+#[derive(Default, Debug)]
+pub struct RangesInclusive<T>(Vec<RangeInclusive<T>>);
+
+fn overlaps<T: PartialOrd>(left: &RangeInclusive<T>, right: &RangeInclusive<T>) -> bool {
+    left.start() <= right.end() && right.start() <= left.end()
+}
+
+fn touches<T: Copy + PartialOrd + Successor>(
+    left: &RangeInclusive<T>,
+    right: &RangeInclusive<T>,
+) -> bool {
+    left.end().checked_successor() == Some(*right.start())
+        || right.end().checked_successor() == Some(*left.start())
+}
+
+impl<T: Copy + Ord + Debug + Successor> RangesInclusive<T> {
+    /// Generates a Vec of ranges after flattening
+    ///
+    /// ```
+    /// use ranges::inclusive::RangesInclusive;
+    ///
+    /// let mut ranges = RangesInclusive::default();
+    ///
+    /// ranges.add_range(5..=10);
+    /// ranges.add_range(9..=12);
+    ///
+    /// assert_eq!(ranges.to_vec(), vec![5..=12]);
+    /// ```
+    pub fn to_vec(&self) -> Vec<RangeInclusive<T>> {
+        let mut results = self.0.clone();
+        results.sort_by_key(|r| *r.start());
+
+        results
+    }
+
+    /// Adds a range to the managed list, expanding existing ranges if there is overlap or if
+    /// they are adjacent (touching).
+    pub fn add_range(&mut self, range: RangeInclusive<T>) {
+        let mut start = *range.start();
+        let mut end = *range.end();
+        let mut remaining = vec![];
+
+        for stored in self.0.drain(..) {
+            if overlaps(&stored, &(start..=end)) || touches(&stored, &(start..=end)) {
+                if *stored.start() < start {
+                    start = *stored.start();
+                }
+                if *stored.end() > end {
+                    end = *stored.end();
+                }
+            } else {
+                remaining.push(stored);
+            }
+        }
+
+        remaining.push(start..=end);
+        self.0 = remaining;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adding_overlapping_ranges() {
+        let mut ranges = RangesInclusive::default();
+
+        ranges.add_range(5..=10);
+        ranges.add_range(9..=12);
+
+        assert_eq!(ranges.to_vec(), vec![5..=12]);
+    }
+
+    #[test]
+    fn test_adding_adjacent_ranges_coalesces_them() {
+        let mut ranges = RangesInclusive::default();
+
+        ranges.add_range(5..=10);
+        ranges.add_range(11..=15);
+
+        assert_eq!(ranges.to_vec(), vec![5..=15]);
+    }
+
+    #[test]
+    fn test_adding_ranges_with_a_gap_stays_separate() {
+        let mut ranges = RangesInclusive::default();
+
+        ranges.add_range(5..=10);
+        ranges.add_range(12..=15);
+
+        assert_eq!(ranges.to_vec(), vec![5..=10, 12..=15]);
+    }
+
+    #[test]
+    fn test_adding_a_range_ending_at_the_types_max_does_not_overflow() {
+        let mut ranges = RangesInclusive::default();
+
+        ranges.add_range(250..=u8::MAX);
+        ranges.add_range(0..=5);
+
+        assert_eq!(ranges.to_vec(), vec![0..=5, 250..=u8::MAX]);
+    }
+}
@@ -17,21 +17,20 @@
 //! ```
 
 use std::fmt::Debug;
-use std::ops::Range;
+use std::ops::{Add, Range};
+
+pub mod inclusive;
 
 /// Structure that manages the expanding list of ranges.
 #[derive(Default, Debug)]
 pub struct Ranges<T>(Vec<Range<T>>);
 
 fn overlaps<T: PartialOrd>(left: &Range<T>, right: &Range<T>) -> bool {
-    left.contains(&right.start)
-        || left.contains(&right.end)
-        || right.contains(&left.start)
-        || right.contains(&left.end)
+    left.start < right.end && right.start < left.end
 }
 
 impl<T: Copy + Ord + Debug> Ranges<T> {
-    /// Generates a Vec of ranges after flattening
+    /// Returns the managed ranges, already sorted by start and disjoint.
     ///
     /// ```
     /// use ranges::*;
@@ -44,59 +43,365 @@ impl<T: Copy + Ord + Debug> Ranges<T> {
     /// assert_eq!(ranges.to_vec(), vec![5..12]);
     /// ```
     pub fn to_vec(&self) -> Vec<Range<T>> {
-        let mut results = self.0.clone();
-        results.sort_by_key(|r| r.start);
-
-        results
+        self.0.clone()
     }
 
     /// Adds a range to the managed list, expanding existing ranges if there is overlap
     pub fn add_range(&mut self, range: Range<T>) {
-        if self.0.is_empty() || self.0.iter().all(|v| !overlaps(v, &range)) {
-            self.0.push(range);
-            return;
+        self.insert(range, false);
+    }
+
+    /// Adds a range to the managed list, expanding existing ranges if there is overlap
+    /// *or* if they are merely adjacent (touching), so the result stays a minimal set of
+    /// disjoint, maximal intervals. Callers who only want the strict overlap-only merging
+    /// of [`Ranges::add_range`] can keep using that instead.
+    ///
+    /// ```
+    /// use ranges::*;
+    ///
+    /// let mut ranges = Ranges::default();
+    ///
+    /// ranges.add_range_coalescing(5..10);
+    /// ranges.add_range_coalescing(10..15);
+    ///
+    /// assert_eq!(ranges.to_vec(), vec![5..15]);
+    /// ```
+    pub fn add_range_coalescing(&mut self, range: Range<T>) {
+        self.insert(range, true);
+    }
+
+    /// Removes a range from the managed list, trimming, splitting, or dropping any stored
+    /// ranges that overlap it. This is the inverse of [`Ranges::add_range`].
+    ///
+    /// ```
+    /// use ranges::*;
+    ///
+    /// let mut ranges = Ranges::default();
+    ///
+    /// ranges.add_range(0..20);
+    /// ranges.remove_range(5..10);
+    ///
+    /// assert_eq!(ranges.to_vec(), vec![0..5, 10..20]);
+    /// ```
+    pub fn remove_range(&mut self, range: Range<T>) {
+        let mut results = vec![];
+
+        for stored in self.0.drain(..) {
+            if !overlaps(&stored, &range) {
+                results.push(stored);
+                continue;
+            }
+
+            if range.start > stored.start {
+                results.push(stored.start..range.start);
+            }
+
+            if range.end < stored.end {
+                results.push(range.end..stored.end);
+            }
         }
 
-        let mut expanded = vec![];
-        for mut range_mut in self.0.iter_mut().filter(|v| overlaps(v, &range)) {
-            if flatten_range(&mut range_mut, &range) {
-                expanded.push(range_mut.clone());
+        self.0 = results;
+    }
+
+    /// Returns the complement of the stored ranges within `bounds`, i.e. the uncovered
+    /// intervals that remain once every stored range has been clamped to `bounds`.
+    ///
+    /// ```
+    /// use ranges::*;
+    ///
+    /// let mut ranges = Ranges::default();
+    ///
+    /// ranges.add_range(5..10);
+    /// ranges.add_range(15..20);
+    ///
+    /// assert_eq!(ranges.gaps(0..25), vec![0..5, 10..15, 20..25]);
+    /// ```
+    pub fn gaps(&self, bounds: Range<T>) -> Vec<Range<T>> {
+        let mut results = vec![];
+        let mut cursor = bounds.start;
+
+        for stored in self.to_vec() {
+            let start = if stored.start > bounds.start {
+                stored.start
+            } else {
+                bounds.start
+            };
+            let end = if stored.end < bounds.end {
+                stored.end
+            } else {
+                bounds.end
+            };
+
+            if start >= end {
+                continue;
+            }
+
+            if start > cursor {
+                results.push(cursor..start);
+            }
+
+            if end > cursor {
+                cursor = end;
             }
         }
 
-        for expand in expanded {
-            self.0.retain(|v| v != &expand);
-            self.add_range(expand);
+        if cursor < bounds.end {
+            results.push(cursor..bounds.end);
         }
+
+        results
+    }
+
+    /// Returns whether any stored range contains `value`.
+    ///
+    /// ```
+    /// use ranges::*;
+    ///
+    /// let mut ranges = Ranges::default();
+    ///
+    /// ranges.add_range(5..10);
+    ///
+    /// assert!(ranges.contains(&7));
+    /// assert!(!ranges.contains(&12));
+    /// ```
+    pub fn contains(&self, value: &T) -> bool {
+        self.0.iter().any(|stored| stored.contains(value))
+    }
+
+    /// Returns every stored range intersecting `range`.
+    ///
+    /// ```
+    /// use ranges::*;
+    ///
+    /// let mut ranges = Ranges::default();
+    ///
+    /// ranges.add_range(0..5);
+    /// ranges.add_range(10..15);
+    /// ranges.add_range(20..25);
+    ///
+    /// assert_eq!(ranges.overlapping(&(3..22)), vec![0..5, 10..15, 20..25]);
+    /// ```
+    pub fn overlapping(&self, range: &Range<T>) -> Vec<Range<T>> {
+        self.0
+            .iter()
+            .filter(|stored| overlaps(stored, range))
+            .cloned()
+            .collect()
     }
-}
 
-fn flatten_range<T: Copy + PartialOrd + Debug>(left: &mut Range<T>, right: &Range<T>) -> bool {
-    let mut expanded = false;
-    match (left.contains(&right.start), left.contains(&right.end)) {
-        (true, true) => (),
-        (true, false) => {
-            left.end = right.end;
-            expanded = true;
+    /// Returns a new `Ranges` containing every range covered by either `self` or `other`.
+    ///
+    /// ```
+    /// use ranges::*;
+    ///
+    /// let mut a = Ranges::default();
+    /// a.add_range(0..5);
+    ///
+    /// let mut b = Ranges::default();
+    /// b.add_range(3..10);
+    ///
+    /// assert_eq!(a.union(&b).to_vec(), vec![0..10]);
+    /// ```
+    pub fn union(&self, other: &Ranges<T>) -> Ranges<T> {
+        let mut result = Ranges(self.0.clone());
+
+        for range in other.0.iter() {
+            result.add_range(range.clone());
         }
-        (false, true) => {
-            left.start = right.start;
-            expanded = true;
+
+        result
+    }
+
+    /// Returns a new `Ranges` containing only the parts covered by both `self` and `other`.
+    ///
+    /// ```
+    /// use ranges::*;
+    ///
+    /// let mut a = Ranges::default();
+    /// a.add_range(0..10);
+    ///
+    /// let mut b = Ranges::default();
+    /// b.add_range(5..15);
+    ///
+    /// assert_eq!(a.intersection(&b).to_vec(), vec![5..10]);
+    /// ```
+    pub fn intersection(&self, other: &Ranges<T>) -> Ranges<T> {
+        let mut result = Ranges(vec![]);
+
+        for left in self.to_vec() {
+            for right in other.to_vec() {
+                let start = if left.start > right.start {
+                    left.start
+                } else {
+                    right.start
+                };
+                let end = if left.end < right.end {
+                    left.end
+                } else {
+                    right.end
+                };
+
+                if start < end {
+                    result.add_range(start..end);
+                }
+            }
         }
-        (false, false) => match (right.contains(&left.start), right.contains(&left.end)) {
-            (true, true) => {
-                left.start = right.start;
-                left.end = right.end;
-                expanded = true;
+
+        result
+    }
+
+    /// Returns a new `Ranges` containing the parts of `self` not covered by `other`.
+    ///
+    /// ```
+    /// use ranges::*;
+    ///
+    /// let mut a = Ranges::default();
+    /// a.add_range(0..10);
+    ///
+    /// let mut b = Ranges::default();
+    /// b.add_range(3..7);
+    ///
+    /// assert_eq!(a.difference(&b).to_vec(), vec![0..3, 7..10]);
+    /// ```
+    pub fn difference(&self, other: &Ranges<T>) -> Ranges<T> {
+        let mut result = Ranges(self.0.clone());
+
+        for range in other.to_vec() {
+            result.remove_range(range);
+        }
+
+        result
+    }
+
+    /// Inserts `range` while maintaining the sorted, disjoint invariant of `self.0`. Binary
+    /// searches for the first stored range that could possibly merge with `range`, then walks
+    /// forward merging every stored range that continues to overlap (or, when
+    /// `coalesce_adjacent` is set, merely touch) it, splicing the expanded result back in a
+    /// single operation. Finding and merging the affected ranges takes O(log n + k), where k is
+    /// the number of ranges merged, rather than rescanning the whole list; the splice itself
+    /// still shifts the stored ranges that follow the merged run.
+    fn insert(&mut self, range: Range<T>, coalesce_adjacent: bool) {
+        let mut start = range.start;
+        let mut end = range.end;
+
+        let lower = self.0.partition_point(|stored| {
+            if coalesce_adjacent {
+                stored.end < start
+            } else {
+                stored.end <= start
             }
-            (true, false) => {
-                left.start = right.start;
-                expanded = true;
+        });
+
+        let mut upper = lower;
+        while upper < self.0.len() {
+            let stored = &self.0[upper];
+            let merges = if coalesce_adjacent {
+                stored.start <= end
+            } else {
+                stored.start < end
+            };
+            if !merges {
+                break;
             }
-            _ => (),
-        },
+
+            if stored.start < start {
+                start = stored.start;
+            }
+            if stored.end > end {
+                end = stored.end;
+            }
+            upper += 1;
+        }
+
+        self.0.splice(lower..upper, std::iter::once(start..end));
+    }
+}
+
+/// A single entry in a [`RangeMapping`], shifting any value landing within `src` by `delta`.
+#[derive(Debug, Clone)]
+pub struct RangeMappingEntry<T> {
+    /// The source range this entry applies to.
+    pub src: Range<T>,
+    /// The amount added to values landing within `src`.
+    pub delta: T,
+}
+
+/// A piecewise offset table used to project stored ranges from one space into another, as in
+/// the classic "seed-to-soil" range remapping problem: source intervals are split across
+/// whichever mappings they straddle, and each resulting piece is shifted by that mapping's
+/// `delta`.
+#[derive(Default, Debug)]
+pub struct RangeMapping<T>(Vec<RangeMappingEntry<T>>);
+
+impl<T: Copy + PartialOrd> RangeMapping<T> {
+    /// Adds a mapping entry covering `src`, shifting any value within it by `delta`.
+    pub fn add_entry(&mut self, src: Range<T>, delta: T) {
+        self.0.push(RangeMappingEntry { src, delta });
+    }
+}
+
+impl<T: Copy + Ord + Debug + Add<Output = T>> Ranges<T> {
+    /// Projects every stored range through `mapping`, splitting a range across whichever
+    /// mapping entries it straddles and shifting each resulting piece by that entry's `delta`.
+    /// Portions matching no entry pass through unchanged. The result is re-normalized through
+    /// [`Ranges::add_range`].
+    ///
+    /// ```
+    /// use ranges::*;
+    ///
+    /// let mut mapping = RangeMapping::default();
+    /// mapping.add_entry(10..20, 5);
+    ///
+    /// let mut ranges = Ranges::default();
+    /// ranges.add_range(0..15);
+    ///
+    /// assert_eq!(ranges.map_through(&mapping).to_vec(), vec![0..10, 15..20]);
+    /// ```
+    pub fn map_through(&self, mapping: &RangeMapping<T>) -> Ranges<T> {
+        let mut result = Ranges(vec![]);
+
+        for stored in self.to_vec() {
+            let mut worklist = vec![stored];
+
+            while let Some(current) = worklist.pop() {
+                if current.start >= current.end {
+                    continue;
+                }
+
+                match mapping
+                    .0
+                    .iter()
+                    .find(|entry| overlaps(&current, &entry.src))
+                {
+                    Some(entry) => {
+                        let start = if current.start > entry.src.start {
+                            current.start
+                        } else {
+                            entry.src.start
+                        };
+                        let end = if current.end < entry.src.end {
+                            current.end
+                        } else {
+                            entry.src.end
+                        };
+
+                        result.add_range((start + entry.delta)..(end + entry.delta));
+
+                        if current.start < start {
+                            worklist.push(current.start..start);
+                        }
+                        if end < current.end {
+                            worklist.push(end..current.end);
+                        }
+                    }
+                    None => result.add_range(current),
+                }
+            }
+        }
+
+        result
     }
-    expanded
 }
 
 #[cfg(test)]
@@ -157,4 +462,254 @@ mod tests {
 
         assert_eq!(ranges.to_vec(), vec![-831125..4366587]);
     }
+
+    #[test]
+    fn test_add_range_merges_several_existing_ranges_at_once() {
+        let mut ranges = Ranges::default();
+
+        ranges.add_range(0..5);
+        ranges.add_range(10..15);
+        ranges.add_range(20..25);
+        ranges.add_range(30..35);
+
+        ranges.add_range(3..22);
+
+        assert_eq!(ranges.to_vec(), vec![0..25, 30..35]);
+    }
+
+    #[test]
+    fn test_add_range_does_not_coalesce_touching_ranges() {
+        let mut ranges = Ranges::default();
+
+        ranges.add_range(5..10);
+        ranges.add_range(10..15);
+
+        assert_eq!(ranges.to_vec(), vec![5..10, 10..15]);
+    }
+
+    #[test]
+    fn test_add_range_coalescing_merges_touching_ranges() {
+        let mut ranges = Ranges::default();
+
+        ranges.add_range_coalescing(5..10);
+        ranges.add_range_coalescing(10..15);
+        ranges.add_range_coalescing(20..25);
+        ranges.add_range_coalescing(15..20);
+
+        assert_eq!(ranges.to_vec(), vec![5..25]);
+    }
+
+    #[test]
+    fn test_add_range_coalescing_still_merges_overlapping_ranges() {
+        let mut ranges = Ranges::default();
+
+        ranges.add_range_coalescing(-5..5);
+        ranges.add_range_coalescing(-3..2);
+
+        assert_eq!(ranges.to_vec(), vec![-5..5]);
+    }
+
+    #[test]
+    fn test_remove_range_splits_a_stored_range() {
+        let mut ranges = Ranges::default();
+
+        ranges.add_range(0..20);
+        ranges.remove_range(5..10);
+
+        assert_eq!(ranges.to_vec(), vec![0..5, 10..20]);
+    }
+
+    #[test]
+    fn test_remove_range_trims_from_either_end() {
+        let mut ranges = Ranges::default();
+
+        ranges.add_range(0..10);
+        ranges.add_range(20..30);
+        ranges.remove_range(-5..5);
+        ranges.remove_range(25..35);
+
+        assert_eq!(ranges.to_vec(), vec![5..10, 20..25]);
+    }
+
+    #[test]
+    fn test_remove_range_drops_fully_covered_ranges() {
+        let mut ranges = Ranges::default();
+
+        ranges.add_range(5..10);
+        ranges.add_range(20..30);
+        ranges.remove_range(0..15);
+
+        assert_eq!(ranges.to_vec(), vec![20..30]);
+    }
+
+    #[test]
+    fn test_remove_range_ignores_non_overlapping_ranges() {
+        let mut ranges = Ranges::default();
+
+        ranges.add_range(5..10);
+        ranges.remove_range(20..30);
+
+        assert_eq!(ranges.to_vec(), vec![5..10]);
+    }
+
+    #[test]
+    fn test_gaps_between_and_around_stored_ranges() {
+        let mut ranges = Ranges::default();
+
+        ranges.add_range(5..10);
+        ranges.add_range(15..20);
+
+        assert_eq!(ranges.gaps(0..25), vec![0..5, 10..15, 20..25]);
+    }
+
+    #[test]
+    fn test_gaps_clamps_ranges_to_bounds() {
+        let mut ranges = Ranges::default();
+
+        ranges.add_range(-10..5);
+        ranges.add_range(8..30);
+
+        assert_eq!(ranges.gaps(0..20), vec![5..8]);
+    }
+
+    #[test]
+    fn test_gaps_with_no_stored_ranges_is_the_full_bounds() {
+        let ranges: Ranges<i32> = Ranges::default();
+
+        assert_eq!(ranges.gaps(0..10), vec![0..10]);
+    }
+
+    #[test]
+    fn test_gaps_with_fully_covered_bounds_is_empty() {
+        let mut ranges = Ranges::default();
+
+        ranges.add_range(0..10);
+
+        assert_eq!(ranges.gaps(2..8), Vec::<std::ops::Range<i32>>::new());
+    }
+
+    #[test]
+    fn test_contains_checks_stored_ranges() {
+        let mut ranges = Ranges::default();
+
+        ranges.add_range(5..10);
+        ranges.add_range(20..25);
+
+        assert!(ranges.contains(&7));
+        assert!(ranges.contains(&20));
+        assert!(!ranges.contains(&10));
+        assert!(!ranges.contains(&15));
+    }
+
+    #[test]
+    fn test_overlapping_returns_intersecting_ranges() {
+        let mut ranges = Ranges::default();
+
+        ranges.add_range(0..5);
+        ranges.add_range(10..15);
+        ranges.add_range(20..25);
+
+        assert_eq!(ranges.overlapping(&(3..22)), vec![0..5, 10..15, 20..25]);
+        assert_eq!(
+            ranges.overlapping(&(6..9)),
+            Vec::<std::ops::Range<i32>>::new()
+        );
+    }
+
+    #[test]
+    fn test_union_combines_both_sets() {
+        let mut a = Ranges::default();
+        a.add_range(0..5);
+        a.add_range(20..25);
+
+        let mut b = Ranges::default();
+        b.add_range(3..10);
+
+        assert_eq!(a.union(&b).to_vec(), vec![0..10, 20..25]);
+    }
+
+    #[test]
+    fn test_intersection_keeps_only_shared_coverage() {
+        let mut a = Ranges::default();
+        a.add_range(0..10);
+        a.add_range(20..30);
+
+        let mut b = Ranges::default();
+        b.add_range(5..15);
+        b.add_range(25..35);
+
+        assert_eq!(a.intersection(&b).to_vec(), vec![5..10, 25..30]);
+    }
+
+    #[test]
+    fn test_intersection_is_empty_when_disjoint() {
+        let mut a = Ranges::default();
+        a.add_range(0..5);
+
+        let mut b = Ranges::default();
+        b.add_range(10..15);
+
+        assert_eq!(
+            a.intersection(&b).to_vec(),
+            Vec::<std::ops::Range<i32>>::new()
+        );
+    }
+
+    #[test]
+    fn test_difference_removes_overlapping_coverage() {
+        let mut a = Ranges::default();
+        a.add_range(0..10);
+
+        let mut b = Ranges::default();
+        b.add_range(3..7);
+
+        assert_eq!(a.difference(&b).to_vec(), vec![0..3, 7..10]);
+    }
+
+    #[test]
+    fn test_map_through_shifts_a_fully_covered_range() {
+        let mut mapping = RangeMapping::default();
+        mapping.add_entry(10..20, 5);
+
+        let mut ranges = Ranges::default();
+        ranges.add_range(12..18);
+
+        assert_eq!(ranges.map_through(&mapping).to_vec(), vec![17..23]);
+    }
+
+    #[test]
+    fn test_map_through_splits_a_range_across_an_entry_boundary() {
+        let mut mapping = RangeMapping::default();
+        mapping.add_entry(10..20, 5);
+
+        let mut ranges = Ranges::default();
+        ranges.add_range(0..15);
+
+        assert_eq!(ranges.map_through(&mapping).to_vec(), vec![0..10, 15..20]);
+    }
+
+    #[test]
+    fn test_map_through_passes_through_unmatched_ranges_unchanged() {
+        let mapping: RangeMapping<i32> = RangeMapping::default();
+
+        let mut ranges = Ranges::default();
+        ranges.add_range(0..5);
+
+        assert_eq!(ranges.map_through(&mapping).to_vec(), vec![0..5]);
+    }
+
+    #[test]
+    fn test_map_through_splits_across_multiple_entries() {
+        let mut mapping = RangeMapping::default();
+        mapping.add_entry(0..10, 100);
+        mapping.add_entry(20..30, 200);
+
+        let mut ranges = Ranges::default();
+        ranges.add_range(5..25);
+
+        assert_eq!(
+            ranges.map_through(&mapping).to_vec(),
+            vec![10..20, 105..110, 220..225]
+        );
+    }
 }